@@ -4,10 +4,55 @@ use sha2::{Digest, Sha256};
 use std::cell::RefCell;
 use std::env;
 use std::rc::{Rc, Weak};
+use std::sync::mpsc::{Receiver, Sender};
 
 // Faster hashmap
 use ahash::AHashMap as HashMap;
 
+// ---------------- Difficulty ----------------
+
+// Target at height 0, analogous to Bitcoin's powLimit. Difficulty is
+// expressed relative to this. Retargeting does not cap the target at this
+// value: a slow epoch can raise the target above it.
+const GENESIS_TARGET: f64 = 1.0e15;
+
+// Retarget every N blocks, expecting each block to take 600s, mirroring
+// Bitcoin's 2016-block / 2-week difficulty adjustment window.
+const RETARGET_INTERVAL: usize = 2016;
+const TARGET_BLOCK_TIME_SECS: usize = 600;
+const EXPECTED_TIMESPAN_SECS: usize = RETARGET_INTERVAL * TARGET_BLOCK_TIME_SECS;
+
+// Walk back RETARGET_INTERVAL - 1 blocks from `parent` and compute the next
+// block's target using Bitcoin's "expected nbits" logic: compare how long
+// the last epoch actually took against the expected timespan and scale the
+// target proportionally, clamped to a 4x adjustment per retarget. The
+// -1 matches Bitcoin's own epoch convention: the epoch spans RETARGET_INTERVAL
+// blocks inclusive of `parent`, so its first block is RETARGET_INTERVAL - 1
+// blocks back, not RETARGET_INTERVAL (which would land one block early and
+// make the very first retarget at height RETARGET_INTERVAL always miss
+// genesis and silently skip adjusting).
+fn next_target(parent: &Rc<Block>) -> f64 {
+    let new_height = parent.height + 1;
+    if !new_height.is_multiple_of(RETARGET_INTERVAL) {
+        return parent.target;
+    }
+
+    let mut epoch_start = parent.clone();
+    for _ in 0..RETARGET_INTERVAL - 1 {
+        match epoch_start.parent.as_ref().and_then(|w| w.upgrade()) {
+            Some(p) => epoch_start = p,
+            // Not enough history yet (near genesis): keep the target as-is.
+            None => return parent.target,
+        }
+    }
+
+    let actual_timespan = parent.time.saturating_sub(epoch_start.time) as f64;
+    let expected_timespan = EXPECTED_TIMESPAN_SECS as f64;
+    let clamped_timespan = actual_timespan.clamp(expected_timespan / 4.0, expected_timespan * 4.0);
+
+    parent.target * clamped_timespan / expected_timespan
+}
+
 // ---------------- Block ----------------
 
 #[derive(Debug)]
@@ -18,6 +63,8 @@ struct Block {
     miner_id: Option<usize>,
     parent: Option<Weak<Block>>, // Weak to avoid deep recursive drop
     parent_hash: [u8; 32],       // cached for quick membership checks
+    target: f64,                 // compact work threshold for this block
+    total_work: f64,             // cumulative chain work, for fork choice
 }
 
 impl Block {
@@ -31,6 +78,17 @@ impl Block {
             Some(p) => p.hash,
             None => [0u8; 32],
         };
+        let target = match parent.as_ref() {
+            Some(p) => next_target(p),
+            None => GENESIS_TARGET,
+        };
+        // Work contributed by this block is its difficulty relative to the
+        // genesis target, accumulated like Ethereum's total difficulty.
+        let work = GENESIS_TARGET / target;
+        let total_work = match parent.as_ref() {
+            Some(p) => p.total_work + work,
+            None => work,
+        };
         let parent_weak = parent.as_ref().map(Rc::downgrade);
 
         // Hash raw bytes instead of formatting strings
@@ -47,10 +105,95 @@ impl Block {
             miner_id,
             parent: parent_weak,
             parent_hash,
+            target,
+            total_work,
         })
     }
 }
 
+// ---------------- Reorgs ----------------
+
+// Is `candidate` the same block as, or a descendant of, `ancestor`? Walks
+// parent links, relying on height always decreasing by exactly one per hop.
+fn is_descendant(candidate: &Rc<Block>, ancestor: &Rc<Block>) -> bool {
+    let mut cur = candidate.clone();
+    while cur.height > ancestor.height {
+        match cur.parent.as_ref().and_then(|w| w.upgrade()) {
+            Some(p) => cur = p,
+            None => return false,
+        }
+    }
+    cur.hash == ancestor.hash
+}
+
+// The result of walking two chain tips back to their lowest common
+// ancestor, modeled on Ethereum's `TreeRoute`: the blocks retracted from
+// the old tip's chain, ordered from the old tip down toward the ancestor,
+// and the blocks enacted to reach the new tip, ordered from the ancestor
+// up to the new tip.
+struct TreeRoute {
+    retracted: Vec<Rc<Block>>, // old_tip .. just above ancestor
+    enacted: Vec<Rc<Block>>,   // just above ancestor .. new_tip
+}
+
+fn tree_route(old_tip: &Rc<Block>, new_tip: &Rc<Block>) -> TreeRoute {
+    let mut retracted = Vec::new();
+    let mut enacted = Vec::new();
+    let mut old_cur = old_tip.clone();
+    let mut new_cur = new_tip.clone();
+
+    while old_cur.height > new_cur.height {
+        retracted.push(old_cur.clone());
+        old_cur = old_cur
+            .parent
+            .as_ref()
+            .and_then(|w| w.upgrade())
+            .expect("chain must reach genesis");
+    }
+    while new_cur.height > old_cur.height {
+        enacted.push(new_cur.clone());
+        new_cur = new_cur
+            .parent
+            .as_ref()
+            .and_then(|w| w.upgrade())
+            .expect("chain must reach genesis");
+    }
+
+    while old_cur.hash != new_cur.hash {
+        retracted.push(old_cur.clone());
+        enacted.push(new_cur.clone());
+        old_cur = old_cur
+            .parent
+            .as_ref()
+            .and_then(|w| w.upgrade())
+            .expect("chain must reach genesis");
+        new_cur = new_cur
+            .parent
+            .as_ref()
+            .and_then(|w| w.upgrade())
+            .expect("chain must reach genesis");
+    }
+
+    enacted.reverse(); // ancestor -> new tip; retracted stays tip -> ancestor
+    TreeRoute { retracted, enacted }
+}
+
+// Per-miner reorg counters, surfaced in the final report.
+#[derive(Default)]
+struct ReorgStats {
+    count: usize,
+    max_depth: usize,
+    depth_histogram: HashMap<usize, usize>,
+}
+
+impl ReorgStats {
+    fn record(&mut self, depth: usize) {
+        self.count += 1;
+        self.max_depth = self.max_depth.max(depth);
+        *self.depth_histogram.entry(depth).or_insert(0) += 1;
+    }
+}
+
 // ---------------- Connection ----------------
 
 struct QueueEntry {
@@ -80,14 +223,14 @@ impl Connection {
         });
     }
 
-    fn send_block(&self, block: Rc<Block>) {
+    fn send_block(&self, t: usize, block: Rc<Block>) {
         if let Some(receiver_rc) = self.receiver.upgrade() {
-            receiver_rc.borrow_mut().receive_block(block);
+            receiver_rc.borrow_mut().receive_block(t, block);
         }
     }
 
     // Borrow-checker safe and cache-friendly tick
-    fn tick(&mut self) {
+    fn tick(&mut self, t: usize) {
         if self.blocks_to_send.is_empty() {
             return;
         }
@@ -110,7 +253,189 @@ impl Connection {
         self.blocks_to_send = remaining;
 
         for block in to_send {
-            self.send_block(block);
+            self.send_block(t, block);
+        }
+    }
+}
+
+// ---------------- Events ----------------
+
+// Structured telemetry a `Miner` can emit as it runs, for external analysis
+// of a simulation's dynamics (block-relay queues emit something similar).
+// `hash` fields carry raw bytes rather than hex, matching `Block::hash`.
+#[derive(Debug, Clone)]
+enum SimEvent {
+    BlockMined { miner: usize, height: usize, time: usize },
+    BlockReceived { miner: usize, hash: [u8; 32] },
+    ChainTipChanged { miner: usize, old_height: usize, new_height: usize },
+    Reorg { miner: usize, depth: usize },
+    StaleBlock { miner: usize, hash: [u8; 32] },
+}
+
+// A `SimEvent` tagged with the simulation tick it occurred at.
+#[derive(Debug, Clone)]
+struct TimedEvent {
+    t: usize,
+    event: SimEvent,
+}
+
+fn hex32(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl TimedEvent {
+    // Newline-delimited JSON: one compact object per event, no external
+    // serde dependency needed for a handful of fixed-shape variants.
+    fn to_json(&self) -> String {
+        match &self.event {
+            SimEvent::BlockMined { miner, height, time } => format!(
+                "{{\"t\":{},\"type\":\"BlockMined\",\"miner\":{},\"height\":{},\"time\":{}}}",
+                self.t, miner, height, time
+            ),
+            SimEvent::BlockReceived { miner, hash } => format!(
+                "{{\"t\":{},\"type\":\"BlockReceived\",\"miner\":{},\"hash\":\"{}\"}}",
+                self.t, miner, hex32(hash)
+            ),
+            SimEvent::ChainTipChanged { miner, old_height, new_height } => format!(
+                "{{\"t\":{},\"type\":\"ChainTipChanged\",\"miner\":{},\"old_height\":{},\"new_height\":{}}}",
+                self.t, miner, old_height, new_height
+            ),
+            SimEvent::Reorg { miner, depth } => format!(
+                "{{\"t\":{},\"type\":\"Reorg\",\"miner\":{},\"depth\":{}}}",
+                self.t, miner, depth
+            ),
+            SimEvent::StaleBlock { miner, hash } => format!(
+                "{{\"t\":{},\"type\":\"StaleBlock\",\"miner\":{},\"hash\":\"{}\"}}",
+                self.t, miner, hex32(hash)
+            ),
+        }
+    }
+}
+
+// Drains whatever events `rx` has buffered, printing one JSON object per
+// line. A stand-in for feeding a timeline into a plotting/analysis tool.
+fn run_ndjson_consumer(rx: &Receiver<TimedEvent>) {
+    for event in rx.try_iter() {
+        println!("{}", event.to_json());
+    }
+}
+
+// ---------------- Strategy ----------------
+
+// A pluggable mining strategy: `Miner` delegates to it for (a) whether/when
+// to announce a block it just found and (b) how to react when a competing
+// block arrives from the network. This lets users assign different miners
+// different behaviors without forking the mining/relay loop itself.
+trait Strategy {
+    fn name(&self) -> &'static str;
+
+    // Called right after `miner` finds a new block extending its own tip,
+    // at simulation tick `t`.
+    fn on_found_block(&mut self, miner: &mut Miner, block: Rc<Block>, t: usize);
+
+    // Called when `block` arrives from the network at tick `t`, after it
+    // has already been recorded in `known_blocks` (i.e. it is not our own
+    // block, and its parent is known).
+    fn on_competing_block(&mut self, miner: &mut Miner, block: Rc<Block>, t: usize);
+}
+
+// Announce every block the instant it's found, and adopt whichever known
+// chain has the most work. This is the behavior every miner had before
+// strategies existed.
+struct HonestStrategy;
+
+impl Strategy for HonestStrategy {
+    fn name(&self) -> &'static str {
+        "Honest"
+    }
+
+    fn on_found_block(&mut self, miner: &mut Miner, block: Rc<Block>, _t: usize) {
+        miner.block_candidates.push(block.clone());
+        miner.announce(block);
+    }
+
+    fn on_competing_block(&mut self, miner: &mut Miner, block: Rc<Block>, _t: usize) {
+        if block.total_work > miner.current_block.total_work {
+            miner.block_candidates.push(block);
+        }
+    }
+}
+
+// Eyal-Sirer selfish mining: withhold newly found blocks on a private
+// chain, and only release them in response to the honest network catching
+// up, so as to orphan more honest work than the attacker's own hashrate
+// would otherwise earn.
+struct SelfishStrategy {
+    // Blocks mined privately but not yet announced, oldest first. Its
+    // length is exactly `lead = private_height - public_height`, since the
+    // miner's private tip (`Miner::current_block`) always extends the last
+    // withheld block (or the network's tip if nothing is withheld).
+    withheld: Vec<Rc<Block>>,
+}
+
+impl SelfishStrategy {
+    fn new() -> Self {
+        Self {
+            withheld: Vec::new(),
+        }
+    }
+
+    // Announce the oldest `n` withheld blocks.
+    fn release(&mut self, miner: &mut Miner, n: usize) {
+        let n = n.min(self.withheld.len());
+        for block in self.withheld.drain(..n) {
+            miner.announce(block);
+        }
+    }
+}
+
+impl Strategy for SelfishStrategy {
+    fn name(&self) -> &'static str {
+        "Selfish"
+    }
+
+    fn on_found_block(&mut self, miner: &mut Miner, block: Rc<Block>, t: usize) {
+        // Extend the private chain silently; don't announce.
+        miner.set_current_block(t, block.clone());
+        self.withheld.push(block);
+    }
+
+    fn on_competing_block(&mut self, miner: &mut Miner, block: Rc<Block>, t: usize) {
+        let lead = self.withheld.len();
+
+        match lead {
+            0 => {
+                // No private lead to defend: fall in line with the network,
+                // but only if it has actually caught up or pulled ahead of
+                // our current tip (>= rather than the honest path's strict
+                // >, since a tie here is the network catching up to us, not
+                // a competitor to ignore) — an asymmetric-latency straggler
+                // with strictly less work than our current tip must not
+                // regress us to a worse chain.
+                if block.total_work >= miner.current_block.total_work {
+                    miner.set_current_block(t, block);
+                }
+            }
+            1 => {
+                // The public chain just drew level with our one withheld
+                // block: publish it immediately to force a race. Same >=
+                // guard as the `lead == 0` arm: only release if the
+                // incoming block actually matches or beats our withheld
+                // block's work, not some stale straggler.
+                if block.total_work >= miner.current_block.total_work {
+                    self.release(miner, 1);
+                }
+            }
+            2 => {
+                // Still ahead even after the honest block: release the
+                // whole private chain to override it outright.
+                self.release(miner, 2);
+            }
+            _ => {
+                // Comfortably ahead: feed the network one block to keep it
+                // racing, while staying exactly one block ahead.
+                self.release(miner, 1);
+            }
         }
     }
 }
@@ -126,16 +451,32 @@ struct Miner {
     connections: Vec<Connection>,
     known_blocks: HashMap<[u8; 32], Rc<Block>>,
     rejected_blocks: HashMap<[u8; 32], Rc<Block>>,
+    // Orphans indexed by the parent hash they're waiting on, so a newly
+    // connected block's children can be found directly instead of
+    // rescanning every orphan.
+    orphans_by_parent: HashMap<[u8; 32], Vec<Rc<Block>>>,
     blocks_mined: usize,
-    probability_per_second: f64,
+    reorg_stats: ReorgStats,
     rng: Xoroshiro128PlusPlus, // fast per-miner RNG
+    // Boxed and wrapped in an Option so it can be temporarily taken out of
+    // `self` while it runs (it needs `&mut Miner` to announce/adopt blocks).
+    strategy: Option<Box<dyn Strategy>>,
+    // Telemetry sink; `None` means events aren't being collected this run.
+    event_tx: Option<Sender<TimedEvent>>,
 }
 
 impl Miner {
-    fn new(id: usize, name: &str, initial_block: Rc<Block>, hashrate_proportion: f64) -> Self {
+    fn new(
+        id: usize,
+        name: &str,
+        initial_block: Rc<Block>,
+        hashrate_proportion: f64,
+        strategy: Box<dyn Strategy>,
+        seed: u64,
+        event_tx: Option<Sender<TimedEvent>>,
+    ) -> Self {
         let mut known_blocks: HashMap<[u8; 32], Rc<Block>> = HashMap::default();
         known_blocks.insert(initial_block.hash, initial_block.clone());
-        let probability_per_second = hashrate_proportion * (1.0 - (-1.0f64 / 600.0).exp());
 
         Self {
             id,
@@ -146,12 +487,29 @@ impl Miner {
             connections: Vec::new(),
             known_blocks,
             rejected_blocks: HashMap::default(),
+            orphans_by_parent: HashMap::default(),
+            strategy: Some(strategy),
             blocks_mined: 0,
-            probability_per_second,
-            rng: Xoroshiro128PlusPlus::from_entropy(),
+            reorg_stats: ReorgStats::default(),
+            rng: Xoroshiro128PlusPlus::seed_from_u64(seed),
+            event_tx,
         }
     }
 
+    fn emit(&self, t: usize, event: SimEvent) {
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(TimedEvent { t, event });
+        }
+    }
+
+    // Instantaneous find-probability, derived from the tip's target rather
+    // than a fixed constant so retargeting actually moves block times.
+    fn probability_per_second(&self) -> f64 {
+        let difficulty = GENESIS_TARGET / self.current_block.target;
+        let expected_interval_secs = TARGET_BLOCK_TIME_SECS as f64 * difficulty;
+        self.hashrate_proportion * (1.0 - (-1.0 / expected_interval_secs).exp())
+    }
+
     fn add_connection(&mut self, other: &Rc<RefCell<Miner>>, delay: usize) {
         self.connections.push(Connection::new(other, delay));
     }
@@ -160,6 +518,36 @@ impl Miner {
         block.miner_id == Some(self.id)
     }
 
+    // Switch `current_block` to `new_tip`, detecting and recording a reorg
+    // whenever the new tip isn't a descendant of the old one.
+    fn set_current_block(&mut self, t: usize, new_tip: Rc<Block>) {
+        if new_tip.hash == self.current_block.hash {
+            return;
+        }
+
+        let old_height = self.current_block.height;
+
+        if !is_descendant(&new_tip, &self.current_block) {
+            let route = tree_route(&self.current_block, &new_tip);
+            let depth = route.retracted.len().max(route.enacted.len());
+            self.reorg_stats.record(depth);
+            self.emit(t, SimEvent::Reorg { miner: self.id, depth });
+            for stale in &route.retracted {
+                self.emit(t, SimEvent::StaleBlock { miner: self.id, hash: stale.hash });
+            }
+        }
+
+        self.emit(
+            t,
+            SimEvent::ChainTipChanged {
+                miner: self.id,
+                old_height,
+                new_height: new_tip.height,
+            },
+        );
+        self.current_block = new_tip;
+    }
+
     // 53-bit precise uniform in [0,1)
     #[inline]
     fn rand_f64(&mut self) -> f64 {
@@ -168,41 +556,42 @@ impl Miner {
         (x as f64) * SCALE
     }
 
-    // Unbiased index in [0, n) using Lemire's method
-    #[inline]
-    fn rand_index(&mut self, n: usize) -> usize {
-        debug_assert!(n > 0);
-        let r = self.rng.next_u64();
-        (((r as u128) * (n as u128)) >> 64) as usize
-    }
-
-    fn evaluate_candidates(&mut self) {
-        let max_height = self.block_candidates.iter().map(|b| b.height).max().unwrap();
+    // Fork choice: the heaviest chain wins, not the tallest one, since
+    // difficulty now varies block to block. Among max-work candidates, our
+    // own block is preferred if one is present (so we don't orphan our own
+    // work over a simultaneously-found competitor); otherwise ties are
+    // broken by first-seen order (the order blocks were pushed into
+    // block_candidates).
+    fn evaluate_candidates(&mut self, t: usize) {
+        let max_work = self
+            .block_candidates
+            .iter()
+            .map(|b| b.total_work)
+            .fold(f64::MIN, f64::max);
 
-        let candidates_max_height: Vec<Rc<Block>> = self
+        let candidates_max_work: Vec<Rc<Block>> = self
             .block_candidates
             .iter()
-            .filter(|b| b.height == max_height)
+            .filter(|b| b.total_work == max_work)
             .cloned()
             .collect();
 
-        if let Some(own_block) = candidates_max_height.iter().find(|b| self.is_mine(b)) {
-            self.current_block = own_block.clone();
+        if let Some(own_block) = candidates_max_work.iter().find(|b| self.is_mine(b)) {
+            self.set_current_block(t, own_block.clone());
             self.block_candidates.clear();
             return;
         }
 
-        let idx = self.rand_index(candidates_max_height.len());
-        self.current_block = candidates_max_height[idx].clone();
+        self.set_current_block(t, candidates_max_work[0].clone());
         self.block_candidates.clear();
     }
 
     fn mine(&mut self, time: usize) {
         if !self.block_candidates.is_empty() {
-            self.evaluate_candidates();
+            self.evaluate_candidates(time);
         }
 
-        if self.rand_f64() < self.probability_per_second {
+        if self.rand_f64() < self.probability_per_second() {
             let found_block = Block::new(
                 self.current_block.height + 1,
                 time,
@@ -210,46 +599,62 @@ impl Miner {
                 Some(self.id),
             );
             self.known_blocks.insert(found_block.hash, found_block.clone());
-            self.block_candidates.push(found_block.clone());
-            self.announce(found_block);
+            self.blocks_mined += 1;
+            self.emit(
+                time,
+                SimEvent::BlockMined {
+                    miner: self.id,
+                    height: found_block.height,
+                    time,
+                },
+            );
+
+            let mut strategy = self.strategy.take().unwrap();
+            strategy.on_found_block(self, found_block, time);
+            self.strategy = Some(strategy);
         }
     }
 
     fn announce(&mut self, block: Rc<Block>) {
-        self.blocks_mined += 1;
         for connection in &mut self.connections {
             connection.queue_block(block.clone());
         }
     }
 
-    fn send_messages(&mut self) {
+    fn send_messages(&mut self, t: usize) {
         for connection in &mut self.connections {
-            connection.tick();
+            connection.tick(t);
         }
     }
 
-    fn refresh_rejects(&mut self) {
-        loop {
-            let mut moved: Option<[u8; 32]> = None;
+    // Connect any orphans waiting on `parent_hash` (which was just inserted
+    // into `known_blocks`), cascading through grandchildren etc. via a work
+    // stack instead of rescanning every buffered orphan.
+    // Returns every block the cascade newly connects (not including the
+    // directly-received block that triggered it), so callers can feed them
+    // through the same candidate/strategy path as a direct arrival.
+    fn connect_orphans(&mut self, parent_hash: [u8; 32]) -> Vec<Rc<Block>> {
+        let mut stack = vec![parent_hash];
+        let mut connected = Vec::new();
 
-            for (reject_hash, reject_block) in self.rejected_blocks.iter() {
-                if self.known_blocks.contains_key(&reject_block.parent_hash) {
-                    moved = Some(*reject_hash);
-                    break;
-                }
-            }
+        while let Some(hash) = stack.pop() {
+            let Some(children) = self.orphans_by_parent.remove(&hash) else {
+                continue;
+            };
 
-            if let Some(key) = moved {
-                if let Some(block) = self.rejected_blocks.remove(&key) {
-                    self.known_blocks.insert(key, block);
-                }
-            } else {
-                break;
+            for child in children {
+                let child_hash = child.hash;
+                self.rejected_blocks.remove(&child_hash);
+                self.known_blocks.insert(child_hash, child.clone());
+                stack.push(child_hash);
+                connected.push(child);
             }
         }
+
+        connected
     }
 
-    fn receive_block(&mut self, block: Rc<Block>) {
+    fn receive_block(&mut self, t: usize, block: Rc<Block>) {
         // Only true for genesis, which we'll never receive.
         assert!(block.parent.is_some());
 
@@ -257,57 +662,288 @@ impl Miner {
             return;
         }
 
+        self.emit(t, SimEvent::BlockReceived { miner: self.id, hash: block.hash });
+
         if !self.known_blocks.contains_key(&block.parent_hash) {
-            self.rejected_blocks.insert(block.hash, block);
+            // Only index it once; a block can arrive more than once (e.g.
+            // relayed by multiple peers) before its parent resolves.
+            if self.rejected_blocks.insert(block.hash, block.clone()).is_none() {
+                self.orphans_by_parent
+                    .entry(block.parent_hash)
+                    .or_default()
+                    .push(block);
+            }
             return;
         }
 
         self.known_blocks.insert(block.hash, block.clone());
-        self.refresh_rejects();
+        let cascaded = self.connect_orphans(block.hash);
 
-        if block.height > self.current_block.height {
-            self.block_candidates.push(block);
+        // Every newly-connected block — the direct arrival and whatever
+        // its resolution cascaded in behind it — needs a shot at becoming
+        // the new tip, or a chain reconnected purely via orphans never
+        // gets evaluated against the current one.
+        let mut strategy = self.strategy.take().unwrap();
+        strategy.on_competing_block(self, block, t);
+        for child in cascaded {
+            strategy.on_competing_block(self, child, t);
         }
+        self.strategy = Some(strategy);
     }
 }
 
+// ---------------- Scenario ----------------
+
+// Describes one miner slot in a scenario: its display name and share of
+// total network hashrate. Strategy assignment is wiring, not data (trait
+// objects aren't serializable), so it's passed to `build_scenario`
+// separately.
+struct MinerConfig {
+    name: String,
+    hashrate_proportion: f64,
+}
+
+// A fully self-contained network description: who's mining, how fast
+// messages move between them, and the master seed driving every miner's
+// RNG, so a run can be reproduced byte-for-byte from this struct alone.
+struct ScenarioConfig {
+    miners: Vec<MinerConfig>,
+    // latency[i][j] is the one-way delay in seconds from miner i to miner
+    // j, or `None` if there is no direct link between them. Entries on the
+    // diagonal are unused.
+    latency: Vec<Vec<Option<usize>>>,
+    seed: u64,
+}
+
+impl ScenarioConfig {
+    // The network this simulator originally shipped with: three miners and
+    // an asymmetric A->C link, pinned to a fixed seed for reproducibility.
+    fn default_scenario() -> Self {
+        Self {
+            miners: vec![
+                MinerConfig {
+                    name: "A".to_string(),
+                    hashrate_proportion: 0.3,
+                },
+                MinerConfig {
+                    name: "B".to_string(),
+                    hashrate_proportion: 0.3,
+                },
+                MinerConfig {
+                    name: "C".to_string(),
+                    hashrate_proportion: 0.4,
+                },
+            ],
+            latency: vec![
+                vec![None, Some(0), Some(5)],
+                vec![Some(0), None, Some(0)],
+                vec![Some(0), Some(0), None],
+            ],
+            seed: 0,
+        }
+    }
+
+    // Parse a minimal line-oriented config file:
+    //   seed <u64>
+    //   miner <name> <hashrate_proportion>
+    //   latency <from_name> <to_name> <delay_seconds>
+    // Blank lines and lines starting with '#' are ignored. `latency` lines
+    // are resolved after the whole file is read, so they may reference
+    // miners declared later in the file.
+    fn load(path: &str) -> Result<Self, String> {
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| format!("reading {path}: {e}"))?;
+
+        let mut names: Vec<String> = Vec::new();
+        let mut hashrates: Vec<f64> = Vec::new();
+        let mut edges: Vec<(String, String, usize)> = Vec::new();
+        let mut seed = 0u64;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                ["seed", value] => {
+                    seed = value.parse().map_err(|_| format!("bad seed: {value}"))?;
+                }
+                ["miner", name, hashrate] => {
+                    names.push(name.to_string());
+                    hashrates.push(
+                        hashrate
+                            .parse()
+                            .map_err(|_| format!("bad hashrate_proportion: {hashrate}"))?,
+                    );
+                }
+                ["latency", from, to, delay] => {
+                    edges.push((
+                        from.to_string(),
+                        to.to_string(),
+                        delay.parse().map_err(|_| format!("bad delay: {delay}"))?,
+                    ));
+                }
+                _ => return Err(format!("unrecognized scenario line: {line}")),
+            }
+        }
+
+        let n = names.len();
+        let mut latency = vec![vec![None; n]; n];
+        for (from, to, delay) in edges {
+            let i = names
+                .iter()
+                .position(|name| *name == from)
+                .ok_or_else(|| format!("unknown miner in latency line: {from}"))?;
+            let j = names
+                .iter()
+                .position(|name| *name == to)
+                .ok_or_else(|| format!("unknown miner in latency line: {to}"))?;
+            latency[i][j] = Some(delay);
+        }
+
+        let miners = names
+            .into_iter()
+            .zip(hashrates)
+            .map(|(name, hashrate_proportion)| MinerConfig {
+                name,
+                hashrate_proportion,
+            })
+            .collect();
+
+        Ok(Self {
+            miners,
+            latency,
+            seed,
+        })
+    }
+}
+
+// Derive a miner's RNG seed from the scenario's master seed via a
+// SplitMix64 step keyed by miner id, giving every miner an independent but
+// fully reproducible substream from a single seed, instead of each miner
+// seeding itself from entropy.
+fn derive_seed(master_seed: u64, miner_id: usize) -> u64 {
+    let mut z = master_seed.wrapping_add(0x9E3779B97F4A7C15u64.wrapping_mul(miner_id as u64 + 1));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Build the miner set and directed connection graph described by `config`,
+// assigning each miner the matching entry of `strategies` in order, so
+// callers decide which miners are honest, selfish, etc.
+fn build_scenario(
+    config: &ScenarioConfig,
+    genesis_block: &Rc<Block>,
+    strategies: Vec<Box<dyn Strategy>>,
+    event_tx: &Option<Sender<TimedEvent>>,
+) -> Vec<Rc<RefCell<Miner>>> {
+    assert_eq!(
+        strategies.len(),
+        config.miners.len(),
+        "one strategy required per configured miner"
+    );
+
+    let miners: Vec<Rc<RefCell<Miner>>> = config
+        .miners
+        .iter()
+        .zip(strategies)
+        .enumerate()
+        .map(|(id, (miner_config, strategy))| {
+            Rc::new(RefCell::new(Miner::new(
+                id,
+                &miner_config.name,
+                genesis_block.clone(),
+                miner_config.hashrate_proportion,
+                strategy,
+                derive_seed(config.seed, id),
+                event_tx.clone(),
+            )))
+        })
+        .collect();
+
+    for (i, row) in config.latency.iter().enumerate() {
+        for (j, delay) in row.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if let Some(delay) = delay {
+                miners[i].borrow_mut().add_connection(&miners[j], *delay);
+            }
+        }
+    }
+
+    miners
+}
+
 // ---------------- Main ----------------
 
 fn main() {
-    // Parse optional argument: block_periods (default 10000)
+    // Parse arguments: an optional positional block_periods (default
+    // 10000), `--events` to emit SimEvents, and `--config <path>` to load a
+    // ScenarioConfig instead of the built-in default network.
     let args: Vec<String> = env::args().collect();
-    let block_periods_to_simulate: usize = if args.len() > 1 {
-        args[1].parse().unwrap_or(10000)
-    } else {
-        10000
-    };
+    let mut block_periods_to_simulate: usize = 10000;
+    let mut emit_events = false;
+    let mut config_path: Option<String> = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--events" => emit_events = true,
+            "--config" => {
+                i += 1;
+                config_path = Some(args.get(i).cloned().unwrap_or_else(|| {
+                    eprintln!("--config requires a path argument");
+                    std::process::exit(1);
+                }));
+            }
+            other => {
+                if let Ok(periods) = other.parse() {
+                    block_periods_to_simulate = periods;
+                }
+            }
+        }
+        i += 1;
+    }
 
     let seconds_to_simulate = block_periods_to_simulate * 600;
 
-    let genesis_block = Block::new(0, 0, None, None);
+    let scenario = match &config_path {
+        Some(path) => ScenarioConfig::load(path).unwrap_or_else(|e| {
+            eprintln!("failed to load scenario config {path}: {e}");
+            std::process::exit(1);
+        }),
+        None => ScenarioConfig::default_scenario(),
+    };
 
-    let miners: Vec<Rc<RefCell<Miner>>> = vec![
-        Rc::new(RefCell::new(Miner::new(0, "A", genesis_block.clone(), 0.3))), // "A" for "Attacker"
-        Rc::new(RefCell::new(Miner::new(1, "B", genesis_block.clone(), 0.3))), // "B" for "Big guy"
-        Rc::new(RefCell::new(Miner::new(2, "C", genesis_block.clone(), 0.4))), // "C" for "Crud"
-    ];
+    let genesis_block = Block::new(0, 0, None, None);
 
-    // A -> B (0s), A -> C (5s)
-    miners[0].borrow_mut().add_connection(&miners[1], 0);
-    miners[0].borrow_mut().add_connection(&miners[2], 5);
+    let (event_tx, event_rx) = if emit_events {
+        let (tx, rx) = std::sync::mpsc::channel();
+        (Some(tx), Some(rx))
+    } else {
+        (None, None)
+    };
 
-    // B -> A (0s), B -> C (0s)
-    miners[1].borrow_mut().add_connection(&miners[0], 0);
-    miners[1].borrow_mut().add_connection(&miners[2], 0);
+    // The first configured miner runs the selfish-mining strategy as the
+    // scenario's attacker; everyone else mines honestly.
+    let strategies: Vec<Box<dyn Strategy>> = (0..scenario.miners.len())
+        .map(|id| -> Box<dyn Strategy> {
+            if id == 0 {
+                Box::new(SelfishStrategy::new())
+            } else {
+                Box::new(HonestStrategy)
+            }
+        })
+        .collect();
 
-    // C -> A (0s), C -> B (0s)
-    miners[2].borrow_mut().add_connection(&miners[0], 0);
-    miners[2].borrow_mut().add_connection(&miners[1], 0);
+    let miners = build_scenario(&scenario, &genesis_block, strategies, &event_tx);
 
     for t in 1..seconds_to_simulate {
         // Announcement phase
         for miner in &miners {
-            miner.borrow_mut().send_messages();
+            miner.borrow_mut().send_messages(t);
         }
         // Mining phase
         for miner in &miners {
@@ -315,12 +951,15 @@ fn main() {
         }
     }
 
-    let labels = ["A (Attacker)", "B (Big guy)", "C (Crud)"];
+    if let Some(rx) = event_rx {
+        run_ndjson_consumer(&rx);
+    }
 
     for (i, miner_rc) in miners.iter().enumerate() {
         let miner = miner_rc.borrow();
 
-        println!("\nMiner {} - {}", i, labels[i]);
+        println!("\nMiner {} - {}", i, miner.name);
+        println!("  Strategy: {}", miner.strategy.as_ref().unwrap().name());
         println!("  Hashrate proportion: {:.1}%", miner.hashrate_proportion * 100.0);
         println!("  Current block height: {}", miner.current_block.height);
         println!("  Total known blocks: {}", miner.known_blocks.len());
@@ -340,7 +979,7 @@ fn main() {
 
         if miner.current_block.height > 0 {
             let pct = (blocks_in_chain as f64) / (miner.current_block.height as f64);
-            println!("  Percentage of main chain: {:.4}%", pct * 100.0);
+            println!("  Revenue (share of main chain): {:.4}%", pct * 100.0);
         }
 
         let stale_blocks = miner.blocks_mined.saturating_sub(blocks_in_chain);
@@ -351,5 +990,140 @@ fn main() {
         };
         println!("  Stale Blocks: {}", stale_blocks);
         println!("  Stale rate: {:.4}", stale_rate);
+
+        println!(
+            "  Reorgs: {} (max depth {})",
+            miner.reorg_stats.count, miner.reorg_stats.max_depth
+        );
+        if !miner.reorg_stats.depth_histogram.is_empty() {
+            let mut depths: Vec<(&usize, &usize)> = miner.reorg_stats.depth_histogram.iter().collect();
+            depths.sort_by_key(|(depth, _)| **depth);
+            let histogram = depths
+                .iter()
+                .map(|(depth, count)| format!("{}:{}", depth, count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("  Reorg depth histogram: {{{}}}", histogram);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build a chain of RETARGET_INTERVAL blocks, genesis included (tip at
+    // height RETARGET_INTERVAL - 1), each spaced `secs_per_block` apart,
+    // genesis-first. `Block::parent` is only a
+    // `Weak`, so every block in the chain must be kept alive by an owning
+    // `Rc` somewhere (as `Miner::known_blocks` does in the real binary) or
+    // its ancestors' `Weak`s dangle the moment the loop variable moves on.
+    fn build_epoch(secs_per_block: usize) -> Vec<Rc<Block>> {
+        let mut chain = vec![Block::new(0, 0, None, None)];
+        for height in 1..RETARGET_INTERVAL {
+            let time = height * secs_per_block;
+            let parent = chain.last().unwrap().clone();
+            chain.push(Block::new(height, time, Some(parent), None));
+        }
+        chain
+    }
+
+    #[test]
+    fn next_target_adjusts_at_first_epoch_boundary() {
+        // Blocks arrived twice as slowly as expected, so the very first
+        // retarget (at height RETARGET_INTERVAL) should ease the target,
+        // not silently keep it pinned at the genesis value.
+        let chain = build_epoch(TARGET_BLOCK_TIME_SECS * 2);
+        let tip = chain.last().unwrap();
+        assert_eq!(tip.height + 1, RETARGET_INTERVAL);
+
+        let next = next_target(tip);
+        assert!(
+            next > tip.target,
+            "first epoch boundary must retarget, got {next} vs parent {}",
+            tip.target
+        );
+    }
+
+    #[test]
+    fn out_of_order_orphan_cascade_reaches_the_tip() {
+        // height 2 arrives before its parent (height 1); once height 1
+        // connects, the cascade through connect_orphans must hand height 2
+        // back through the strategy path too, not just height 1.
+        let genesis = Block::new(0, 0, None, None);
+        let height1 = Block::new(1, 10, Some(genesis.clone()), Some(0));
+        let height2 = Block::new(2, 20, Some(height1.clone()), Some(0));
+
+        let mut miner = Miner::new(1, "B", genesis.clone(), 0.0, Box::new(HonestStrategy), 42, None);
+
+        miner.receive_block(0, height2.clone());
+        assert_eq!(miner.current_block.hash, genesis.hash, "orphan must not move the tip yet");
+
+        miner.receive_block(0, height1.clone());
+        miner.evaluate_candidates(0);
+
+        assert_eq!(
+            miner.current_block.hash, height2.hash,
+            "cascaded orphan must be evaluated as a fork-choice candidate"
+        );
+    }
+
+    #[test]
+    fn tree_route_and_is_descendant_find_the_common_ancestor() {
+        // genesis -> a1 -> a2 (chain A)
+        //         -> b1        (chain B, a 1-block fork off genesis)
+        let genesis = Block::new(0, 0, None, None);
+        let a1 = Block::new(1, 10, Some(genesis.clone()), Some(0));
+        let a2 = Block::new(2, 20, Some(a1.clone()), Some(0));
+        let b1 = Block::new(1, 15, Some(genesis.clone()), Some(1));
+
+        assert!(is_descendant(&a2, &genesis));
+        assert!(is_descendant(&a2, &a1));
+        assert!(!is_descendant(&b1, &a1));
+
+        let route = tree_route(&a2, &b1);
+        assert_eq!(
+            route.retracted.iter().map(|b| b.hash).collect::<Vec<_>>(),
+            vec![a2.hash, a1.hash]
+        );
+        assert_eq!(route.enacted.iter().map(|b| b.hash).collect::<Vec<_>>(), vec![b1.hash]);
+    }
+
+    #[test]
+    fn switching_to_a_sibling_chain_records_a_reorg() {
+        let genesis = Block::new(0, 0, None, None);
+        let a1 = Block::new(1, 10, Some(genesis.clone()), Some(0));
+        let a2 = Block::new(2, 20, Some(a1.clone()), Some(0));
+        let b1 = Block::new(1, 15, Some(genesis.clone()), Some(1));
+
+        let mut miner = Miner::new(2, "C", genesis.clone(), 0.0, Box::new(HonestStrategy), 7, None);
+        miner.set_current_block(0, a2.clone());
+        assert_eq!(miner.reorg_stats.count, 0, "extending from genesis is not a reorg");
+
+        miner.set_current_block(1, b1.clone());
+        assert_eq!(miner.reorg_stats.count, 1);
+        assert_eq!(miner.reorg_stats.max_depth, 2, "depth is the longer of retracted/enacted");
+    }
+
+    #[test]
+    fn selfish_strategy_releases_withheld_block_on_a_tie() {
+        let genesis = Block::new(0, 0, None, None);
+        // Strategy placeholder never invoked in this test; what's under
+        // test is a standalone SelfishStrategy driven directly below.
+        let mut miner = Miner::new(0, "A", genesis.clone(), 0.0, Box::new(HonestStrategy), 1, None);
+        let mut strategy = SelfishStrategy::new();
+
+        let private = Block::new(1, 10, Some(genesis.clone()), Some(0));
+        strategy.on_found_block(&mut miner, private.clone(), 10);
+        assert_eq!(miner.current_block.hash, private.hash, "private block extends our tip silently");
+        assert_eq!(strategy.withheld.len(), 1);
+
+        // A competing block at the same height, same work: the public
+        // chain has drawn level with our one withheld block.
+        let competing = Block::new(1, 12, Some(genesis.clone()), Some(1));
+        strategy.on_competing_block(&mut miner, competing, 12);
+
+        assert!(strategy.withheld.is_empty(), "a tie forces release of the withheld block");
+        assert_eq!(miner.current_block.hash, private.hash, "releasing doesn't change our own tip");
     }
 }